@@ -0,0 +1,160 @@
+//! Ambient sounds authored in TrenchBroom.
+//!
+//! Unlike [`WorldEmitter`](super::world_emitter::WorldEmitter), which picks from a
+//! fixed list of sounds, an [`AmbientSound`] can play any sample in the assets
+//! folder and controls its own falloff.
+
+use bevy::prelude::*;
+use bevy_seedling::{
+	firewheel::dsp::distance_attenuation::{DistanceAttenuation, DistanceModel},
+	prelude::*,
+};
+use bevy_trenchbroom::prelude::*;
+
+use crate::{audio::SpatialPool, menus::Menu};
+
+pub(super) fn plugin(app: &mut App) {
+	app.add_observer(spawn_ambient_sound)
+		.add_systems(OnExit(Menu::None), pause_ambient_sounds)
+		.add_systems(OnEnter(Menu::None), play_ambient_sounds);
+}
+
+/// With the linear model, the sound fades to silence at this multiple of its radius.
+const LINEAR_FALLOFF: f32 = 10.0;
+
+/// A sound placed in the level.
+#[point_class(base(Transform, Visibility))]
+pub(crate) struct AmbientSound {
+	/// Path of the sample, relative to the assets folder.
+	#[class(must_set)]
+	sample: String,
+	/// Volume in decibels.
+	volume: f32,
+	/// Whether the sound loops.
+	looping: bool,
+	/// Within this distance the sound plays at full volume.
+	/// Beyond it, the sound fades according to `attenuation`.
+	radius: f32,
+	/// How the sound fades beyond `radius`.
+	attenuation: AttenuationModel,
+}
+
+impl Default for AmbientSound {
+	fn default() -> Self {
+		Self {
+			sample: String::new(),
+			volume: 0.0,
+			looping: true,
+			radius: 2.0,
+			attenuation: AttenuationModel::Inverse,
+		}
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Reflect, FgdType)]
+pub(crate) enum AttenuationModel {
+	/// Inverse (realistic)
+	Inverse,
+	/// Linear (silent at 10x radius)
+	Linear,
+	/// Exponential
+	Exponential,
+}
+
+impl AmbientSound {
+	fn distance_attenuation(&self) -> DistanceAttenuation {
+		let radius = self.radius.max(0.01);
+		DistanceAttenuation {
+			distance_model: match self.attenuation {
+				AttenuationModel::Inverse => DistanceModel::Inverse,
+				AttenuationModel::Linear => DistanceModel::Linear,
+				AttenuationModel::Exponential => DistanceModel::Exponential,
+			},
+			reference_distance: radius,
+			max_distance: radius * LINEAR_FALLOFF,
+			..default()
+		}
+	}
+}
+
+fn spawn_ambient_sound(
+	trigger: On<Insert, AmbientSound>,
+	sounds: Query<&AmbientSound>,
+	server: Res<AssetServer>,
+	mut commands: Commands,
+) -> Result {
+	let sound = sounds.get(trigger.entity)?;
+
+	let mut player =
+		SamplePlayer::new(server.load(&sound.sample)).with_volume(Volume::Decibels(sound.volume));
+	if sound.looping {
+		player = player.looping();
+	}
+
+	commands.entity(trigger.entity).insert((
+		player,
+		PlaybackSettings::default().remove(),
+		SpatialPool,
+		sample_effects![SpatialBasicNode {
+			distance_attenuation: sound.distance_attenuation(),
+			..default()
+		}],
+	));
+
+	Ok(())
+}
+
+fn pause_ambient_sounds(sounds: Query<&mut PlaybackSettings, With<AmbientSound>>) {
+	for mut sound in sounds {
+		sound.pause();
+	}
+}
+
+fn play_ambient_sounds(sounds: Query<&mut PlaybackSettings, With<AmbientSound>>) {
+	// Finished one-shot sounds have their playback components removed,
+	// so they aren't started over here.
+	for mut sound in sounds {
+		if !*sound.play {
+			sound.play_from = PlayFrom::Resume;
+			sound.play();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_attenuation_model() {
+		assert_eq!(
+			AttenuationModel::fgd_parse("Linear").unwrap(),
+			AttenuationModel::Linear
+		);
+		assert!(AttenuationModel::fgd_parse("linear").is_err());
+	}
+
+	#[test]
+	fn radius_sets_attenuation_range() {
+		let sound = AmbientSound {
+			radius: 3.0,
+			attenuation: AttenuationModel::Linear,
+			..default()
+		};
+		let attenuation = sound.distance_attenuation();
+
+		assert_eq!(attenuation.distance_model, DistanceModel::Linear);
+		assert_eq!(attenuation.gain(3.0), 1.0);
+		assert_eq!(attenuation.gain(30.0), 0.0);
+	}
+
+	#[test]
+	fn degenerate_radius_is_clamped() {
+		let sound = AmbientSound {
+			radius: -1.0,
+			..default()
+		};
+
+		assert!(sound.distance_attenuation().reference_distance > 0.0);
+	}
+}
@@ -3,17 +3,19 @@ use bevy_seedling::firewheel::nodes::svf::SvfNode;
 use bevy_seedling::prelude::*;
 
 /// Sets up all the components required to start audio playback at a particular time.
+///
+/// Players which are started at the same `start` instant begin on the same sample.
 pub fn play_at(
 	player: SamplePlayer,
 	time: &Time<Audio>,
-	delay: f64,
+	start: InstantSeconds,
 ) -> (SamplePlayer, PlaybackSettings, AudioEvents) {
 	let mut events = AudioEvents::new(time);
 	let settings = PlaybackSettings {
 		play: Notify::new(false),
 		..Default::default()
 	};
-	settings.play_at(None, time.delay(DurationSeconds(delay)), &mut events);
+	settings.play_at(None, start, &mut events);
 
 	(player, settings, events)
 }
@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 use bevy_seedling::{
+	firewheel::clock::{MusicalTransport, StaticTransport},
 	pool::{CompletionReason, Sampler},
 	prelude::*,
 	sample::QueuedSample,
@@ -7,6 +8,10 @@ use bevy_seedling::{
 
 use crate::audio::{MusicPool, animation::play_at};
 
+/// How far in the future the layers are scheduled to start, so that they all
+/// reach the audio thread before their start time.
+const START_DELAY: DurationSeconds = DurationSeconds(0.2);
+
 pub fn plugin(app: &mut App) {
 	app.add_systems(PostUpdate, LayeredMusic::update_layers)
 		.add_observer(stop_music)
@@ -91,6 +96,84 @@ pub struct Layer(pub Handle<AudioSample>);
 #[derive(Component)]
 struct ActiveLayer;
 
+/// The tempo of a [`LayeredMusic`].
+///
+/// When present, the music gets a [`MusicGrid`] once it starts playing, and
+/// layers only fade in and out on its bar boundaries.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Tempo {
+	pub beats_per_minute: f64,
+	pub beats_per_bar: f64,
+}
+
+/// The musical grid a playing [`LayeredMusic`] with a [`Tempo`] is aligned to.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct MusicGrid {
+	transport: MusicalTransport,
+	/// The instant the layers started playing (musical time of `0`).
+	start: InstantSeconds,
+	beats_per_bar: f64,
+}
+
+impl MusicGrid {
+	/// How many bars a layer takes to fade in or out.
+	const FADE_BARS: f64 = 1.0;
+	/// Bar boundaries closer than this are skipped, since a fade scheduled for
+	/// them might not reach the audio thread in time.
+	const MIN_FADE_LEAD: DurationSeconds = DurationSeconds(0.05);
+
+	pub fn new(tempo: Tempo, start: InstantSeconds) -> Self {
+		Self {
+			transport: MusicalTransport::Static(StaticTransport::new(tempo.beats_per_minute)),
+			start,
+			beats_per_bar: tempo.beats_per_bar,
+		}
+	}
+
+	/// The first bar boundary that is at least [`Self::MIN_FADE_LEAD`] after
+	/// `now`.
+	///
+	/// Before the music starts, this is the instant it starts at.
+	pub fn next_bar(&self, now: InstantSeconds) -> InstantSeconds {
+		let earliest = now + Self::MIN_FADE_LEAD;
+		if earliest <= self.start {
+			return self.start;
+		}
+
+		let beats = self
+			.transport
+			.seconds_to_musical(earliest, self.start, 1.0)
+			.0;
+		let bar = (beats / self.beats_per_bar).ceil();
+
+		self.transport
+			.musical_to_seconds(InstantMusical(bar * self.beats_per_bar), self.start, 1.0)
+	}
+
+	/// The duration of the given number of bars.
+	pub fn bars(&self, bars: f64) -> DurationSeconds {
+		let end = self.transport.musical_to_seconds(
+			InstantMusical(bars * self.beats_per_bar),
+			self.start,
+			1.0,
+		);
+		end - self.start
+	}
+
+	/// Fade a layer to `target` over [`Self::FADE_BARS`], starting on the next
+	/// bar.
+	fn fade(
+		&self,
+		node: &VolumeNode,
+		target: Volume,
+		time: &Time<Audio>,
+		events: &mut AudioEvents,
+	) {
+		let start = self.next_bar(time.now());
+		node.fade_at(target, start, start + self.bars(Self::FADE_BARS), events);
+	}
+}
+
 #[derive(Component)]
 pub struct Intro {
 	pub sample: Handle<AudioSample>,
@@ -110,64 +193,72 @@ pub struct StopLayeredMusic(pub Entity);
 
 fn play_music(
 	trigger: On<PlayLayeredMusic>,
-	music: Query<(&LayeredMusic, Option<&Intro>, &Children)>,
+	music: Query<(&LayeredMusic, Option<&Intro>, Option<&Tempo>, &Children)>,
 	layers: Query<&Layer>,
 	time: Res<Time<Audio>>,
 	mut commands: Commands,
 ) -> Result {
-	let (amount, intro, children) = music.get(trigger.0)?;
+	let (amount, intro, tempo, children) = music.get(trigger.0)?;
 	let default_volume = Volume::Decibels(9.0);
 
 	commands.entity(trigger.0).insert(ActiveMusic);
 
-	match intro {
-		Some(intro) => {
-			commands.entity(trigger.0).insert((
-				MusicPool,
-				SamplePlayer::new(intro.sample.clone()).with_volume(default_volume),
-				PlaybackSettings::default().remove(),
-			));
-
-			for entity in children.iter() {
-				let layer = layers.get(entity)?;
-				let (player, settings, events) = play_at(
-					SamplePlayer::new(layer.0.clone())
-						.looping()
-						.with_volume(default_volume),
-					&time,
-					intro.duration.0,
-				);
-
-				commands.entity(entity).insert((
-					MusicPool,
-					player,
-					settings.remove(),
-					events,
-					sample_effects![VolumeNode::from_linear(0.0)],
-				));
-			}
-		}
-		None => {
-			for entity in children.iter() {
-				let layer = layers.get(entity)?;
-				commands.entity(entity).insert((
-					MusicPool,
-					SamplePlayer::new(layer.0.clone())
-						.looping()
-						.with_volume(default_volume),
-					PlaybackSettings::default().remove(),
-					sample_effects![VolumeNode::from_linear(0.0)],
-				));
-			}
-		}
+	let mut start = time.delay(START_DELAY);
+	if let Some(intro) = intro {
+		let (player, settings, events) = play_at(
+			SamplePlayer::new(intro.sample.clone()).with_volume(default_volume),
+			&time,
+			start,
+		);
+		commands
+			.entity(trigger.0)
+			.insert((MusicPool, player, settings.remove(), events));
+
+		start = start + intro.duration;
+	}
+
+	if let Some(&tempo) = tempo {
+		commands
+			.entity(trigger.0)
+			.insert(MusicGrid::new(tempo, start));
 	}
 
+	let mut active = Vec::with_capacity(children.len());
 	amount.iter_layers(children.iter().map(|e| (e, false)), |entity, new_state| {
 		if let Some(true) = new_state {
-			commands.entity(entity).insert(ActiveLayer);
+			active.push(entity);
 		}
 	})?;
 
+	// All layers share the same start instant, so they start on the same
+	// sample and can never drift apart.
+	for entity in children.iter() {
+		let layer = layers.get(entity)?;
+		let (player, settings, events) = play_at(
+			SamplePlayer::new(layer.0.clone())
+				.looping()
+				.with_volume(default_volume),
+			&time,
+			start,
+		);
+
+		// The initially active layers start at full volume, so their first
+		// downbeat isn't faded.
+		let is_active = active.contains(&entity);
+		let volume = if is_active { 1.0 } else { 0.0 };
+
+		commands.entity(entity).insert((
+			MusicPool,
+			player,
+			settings.remove(),
+			events,
+			sample_effects![VolumeNode::from_linear(volume)],
+		));
+		if is_active {
+			commands.entity(entity).insert(ActiveLayer);
+		}
+	}
+
 	Ok(())
 }
 
@@ -187,7 +278,9 @@ fn stop_music(
 		});
 	}
 
-	commands.entity(trigger.0).remove::<ActiveMusic>();
+	commands
+		.entity(trigger.0)
+		.remove::<(ActiveMusic, MusicGrid)>();
 
 	for entity in children.iter() {
 		if layers.get(entity)? {
@@ -204,26 +297,93 @@ fn stop_music(
 
 fn add_active(
 	trigger: On<Add, ActiveLayer>,
-	layer: Query<&SampleEffects>,
+	layer: Query<(&SampleEffects, &ChildOf)>,
+	grids: Query<&MusicGrid>,
 	mut volume: Query<(&VolumeNode, &mut AudioEvents)>,
+	time: Res<Time<Audio>>,
 ) -> Result {
-	let layer = layer.get(trigger.entity)?;
-	let (node, mut events) = volume.get_effect_mut(layer)?;
+	let (effects, child_of) = layer.get(trigger.entity)?;
+	let (node, mut events) = volume.get_effect_mut(effects)?;
 
-	node.fade_to(Volume::Linear(1.0), DurationSeconds(1.0), &mut events);
+	match grids.get(child_of.parent()) {
+		Ok(grid) => grid.fade(node, Volume::Linear(1.0), &time, &mut events),
+		Err(_) => node.fade_to(Volume::Linear(1.0), DurationSeconds(1.0), &mut events),
+	}
 	Ok(())
 }
 
 fn remove_active(
 	trigger: On<Remove, ActiveLayer>,
-	layer: Query<&SampleEffects>,
+	layer: Query<(&SampleEffects, &ChildOf)>,
+	grids: Query<&MusicGrid>,
 	mut volume: Query<(&VolumeNode, &mut AudioEvents)>,
+	time: Res<Time<Audio>>,
 ) -> Result {
-	let Ok(layer) = layer.get(trigger.entity) else {
+	let Ok((effects, child_of)) = layer.get(trigger.entity) else {
 		return Ok(());
 	};
-	let (node, mut events) = volume.get_effect_mut(layer)?;
+	let (node, mut events) = volume.get_effect_mut(effects)?;
 
-	node.fade_to(Volume::Linear(0.0), DurationSeconds(1.0), &mut events);
+	match grids.get(child_of.parent()) {
+		Ok(grid) => grid.fade(node, Volume::Linear(0.0), &time, &mut events),
+		Err(_) => node.fade_to(Volume::Linear(0.0), DurationSeconds(1.0), &mut events),
+	}
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// At 120 BPM in 4/4, a bar lasts exactly two seconds.
+	fn grid() -> MusicGrid {
+		MusicGrid::new(
+			Tempo {
+				beats_per_minute: 120.0,
+				beats_per_bar: 4.0,
+			},
+			InstantSeconds(10.0),
+		)
+	}
+
+	#[test]
+	fn fades_start_on_the_next_bar() {
+		let grid = grid();
+
+		assert_eq!(grid.next_bar(InstantSeconds(10.5)), InstantSeconds(12.0));
+		assert_eq!(grid.next_bar(InstantSeconds(13.0)), InstantSeconds(14.0));
+		assert_eq!(grid.next_bar(InstantSeconds(17.9)), InstantSeconds(18.0));
+	}
+
+	#[test]
+	fn fades_skip_bars_that_are_too_close() {
+		let grid = grid();
+
+		assert_eq!(grid.next_bar(InstantSeconds(11.99)), InstantSeconds(14.0));
+		assert_eq!(grid.next_bar(InstantSeconds(12.0)), InstantSeconds(14.0));
+	}
+
+	#[test]
+	fn fades_before_the_music_starts_begin_with_it() {
+		let grid = grid();
+
+		assert_eq!(grid.next_bar(InstantSeconds(3.0)), InstantSeconds(10.0));
+		assert_eq!(grid.next_bar(InstantSeconds(9.9)), InstantSeconds(10.0));
+		// Once the start is too close, the first bar line after it is used.
+		assert_eq!(grid.next_bar(InstantSeconds(9.99)), InstantSeconds(12.0));
+	}
+
+	#[test]
+	fn bar_duration_follows_tempo() {
+		assert_eq!(grid().bars(1.0), DurationSeconds(2.0));
+
+		let breakcore = MusicGrid::new(
+			Tempo {
+				beats_per_minute: 180.0,
+				beats_per_bar: 4.0,
+			},
+			InstantSeconds(0.0),
+		);
+		assert_eq!(breakcore.bars(1.0), DurationSeconds(4.0 / 3.0));
+	}
+}
@@ -5,10 +5,12 @@ use bevy_seedling::prelude::*;
 use crate::menus::Menu;
 use animation::AnimateCutoff;
 
+pub(crate) mod ambient_sound;
 pub(crate) mod animation;
 pub(crate) mod doppler;
 pub(crate) mod layers;
 pub(crate) mod perceptual;
+pub(crate) mod reverb_zone;
 pub(crate) mod world_emitter;
 
 pub(super) fn plugin(app: &mut App) {
@@ -16,6 +18,8 @@ pub(super) fn plugin(app: &mut App) {
 		layers::plugin,
 		doppler::DopplerPlugin,
 		world_emitter::EmitterPlugin,
+		ambient_sound::plugin,
+		reverb_zone::plugin,
 	))
 	.add_systems(Startup, initialize_audio)
 	.register_node::<SvfNode<2>>()
@@ -102,7 +106,10 @@ fn initialize_audio(server: Res<AssetServer>, mut commands: Commands) {
 		.spawn((
 			Name::new("SFX audio sampler pool"),
 			SamplerPool(SpatialPool),
-			sample_effects![(SpatialBasicNode::default(), SpatialScale(Vec3::splat(2.0)))],
+			sample_effects![
+				(SpatialBasicNode::default(), SpatialScale(Vec3::splat(2.0))),
+				SendNode::new(Volume::UNITY_GAIN, reverb_zone::ReverbBus),
+			],
 			VolumeNode {
 				volume: DEFAULT_POOL_VOLUME,
 				..default()
@@ -131,6 +138,11 @@ fn silly_breakcore_layers(server: &AssetServer) -> impl Bundle {
 		Name::new("Silly Breakcore"),
 		LayeredMusic { amount: 0.0 },
 		// optional
+		Tempo {
+			beats_per_minute: 180.0,
+			beats_per_bar: 4.0,
+		},
+		// optional
 		Intro {
 			sample: server.load("audio/music/silly-breakcore/intro.wav"),
 			duration: DurationSeconds(4.0 / 3.0),
@@ -0,0 +1,283 @@
+//! Reverb zones authored in TrenchBroom.
+//!
+//! Spatial sounds send a copy of their signal to the [`ReverbBus`]. The bus is
+//! silent until the listener walks into a [`ReverbZone`], at which point it
+//! fades up to the zone's wet level and takes on the zone's preset. When zones
+//! overlap, the innermost (smallest) one wins.
+
+use avian3d::prelude::{ColliderAabb, CollisionLayers, LayerMask, Sensor};
+use bevy::{
+	ecs::{lifecycle::HookContext, world::DeferredWorld},
+	prelude::*,
+};
+use bevy_seedling::{prelude::*, spatial::SpatialListener3D};
+use bevy_trenchbroom::prelude::*;
+
+use crate::third_party::avian3d::CollisionLayer;
+
+pub(super) fn plugin(app: &mut App) {
+	app.init_resource::<ActiveReverbZone>()
+		.add_systems(Startup, spawn_reverb_bus)
+		.add_systems(
+			Update,
+			(
+				find_active_reverb_zone,
+				crossfade_reverb.run_if(resource_changed::<ActiveReverbZone>),
+			)
+				.chain(),
+		);
+}
+
+/// How long it takes to fade between zones.
+const CROSSFADE: DurationSeconds = DurationSeconds(0.75);
+
+/// The bus all spatial sounds send their reverb signal to.
+#[derive(NodeLabel, Reflect, PartialEq, Eq, Debug, Hash, Clone)]
+#[reflect(Component)]
+pub(crate) struct ReverbBus;
+
+/// Marks the reverb node fed by the [`ReverbBus`].
+#[derive(Component)]
+struct ReverbBusReverb;
+
+/// A brush volume which adds reverb to spatial sounds while the listener is inside it.
+///
+/// Zones are treated as their axis-aligned bounds, so keep the brushes box-shaped.
+#[solid_class(base(Transform, Visibility))]
+#[component(on_insert = ReverbZone::on_insert)]
+pub(crate) struct ReverbZone {
+	/// The level of the reverb in decibels while the listener is inside this zone.
+	wet_level: f32,
+	/// The character of the reverb.
+	preset: ReverbPreset,
+}
+
+impl Default for ReverbZone {
+	fn default() -> Self {
+		Self {
+			wet_level: -6.0,
+			preset: ReverbPreset::Room,
+		}
+	}
+}
+
+impl ReverbZone {
+	fn on_insert(mut world: DeferredWorld, ctx: HookContext) {
+		if world.is_scene_world() {
+			return;
+		}
+		// Only the bounds of the brush are needed, so make it invisible and
+		// keep it from blocking anything.
+		world.commands().entity(ctx.entity).insert((
+			Visibility::Hidden,
+			Sensor,
+			CollisionLayers::new([CollisionLayer::Sensor], LayerMask::NONE),
+		));
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Reflect, FgdType)]
+pub(crate) enum ReverbPreset {
+	/// Small room
+	Room,
+	/// Large hall
+	Hall,
+	/// Tiled bathroom or corridor
+	Tiled,
+	/// Cave
+	Cave,
+}
+
+impl ReverbPreset {
+	fn apply(self, reverb: &mut FreeverbNode) {
+		let (room_size, damping) = match self {
+			Self::Room => (0.4, 0.6),
+			Self::Hall => (0.8, 0.5),
+			Self::Tiled => (0.6, 0.15),
+			Self::Cave => (0.95, 0.3),
+		};
+		reverb.room_size = room_size;
+		reverb.damping = damping;
+	}
+}
+
+/// The zone the listener is currently in, if any.
+#[derive(Resource, Debug, Default, PartialEq, Eq)]
+struct ActiveReverbZone(Option<Entity>);
+
+fn spawn_reverb_bus(mut commands: Commands) {
+	commands
+		.spawn((
+			ReverbBus,
+			VolumeNode::from_linear(0.0),
+			Name::new("Reverb Bus"),
+		))
+		// The send is faded before the reverb so that the tail rings out
+		// naturally when leaving a zone.
+		.chain_node((FreeverbNode::default(), ReverbBusReverb))
+		.connect(SoundEffectsBus);
+}
+
+/// The world-space bounds of a reverb zone.
+#[derive(Clone, Copy, Debug)]
+struct ZoneBounds {
+	min: Vec3,
+	max: Vec3,
+}
+
+impl ZoneBounds {
+	fn contains(&self, point: Vec3) -> bool {
+		point.cmpge(self.min).all() && point.cmple(self.max).all()
+	}
+
+	fn volume(&self) -> f32 {
+		(self.max - self.min).max(Vec3::ZERO).element_product()
+	}
+}
+
+/// The smallest of the zones containing `point`.
+fn innermost_zone(
+	point: Vec3,
+	zones: impl IntoIterator<Item = (Entity, ZoneBounds)>,
+) -> Option<Entity> {
+	zones
+		.into_iter()
+		.filter(|(_, bounds)| bounds.contains(point))
+		.min_by(|(a, a_bounds), (b, b_bounds)| {
+			// Ties are broken by entity so that the result doesn't flicker.
+			a_bounds
+				.volume()
+				.total_cmp(&b_bounds.volume())
+				.then(a.cmp(b))
+		})
+		.map(|(entity, _)| entity)
+}
+
+fn find_active_reverb_zone(
+	listener: Option<Single<&GlobalTransform, With<SpatialListener3D>>>,
+	zones: Query<(Entity, &ColliderAabb), With<ReverbZone>>,
+	mut active: ResMut<ActiveReverbZone>,
+) {
+	let zone = listener.and_then(|listener| {
+		innermost_zone(
+			listener.translation(),
+			zones.iter().map(|(entity, aabb)| {
+				(
+					entity,
+					ZoneBounds {
+						min: aabb.min,
+						max: aabb.max,
+					},
+				)
+			}),
+		)
+	});
+	active.set_if_neq(ActiveReverbZone(zone));
+}
+
+fn crossfade_reverb(
+	active: Res<ActiveReverbZone>,
+	zones: Query<&ReverbZone>,
+	bus: Single<(&VolumeNode, &mut AudioEvents), With<ReverbBus>>,
+	mut reverb: Single<&mut FreeverbNode, With<ReverbBusReverb>>,
+) {
+	let (volume, mut events) = bus.into_inner();
+
+	let target = match active.0.and_then(|entity| zones.get(entity).ok()) {
+		Some(zone) => {
+			zone.preset.apply(&mut reverb);
+			Volume::Decibels(zone.wet_level)
+		}
+		None => Volume::SILENT,
+	};
+
+	volume.fade_to(target, CROSSFADE, &mut events);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn cube(center: Vec3, half_size: f32) -> ZoneBounds {
+		ZoneBounds {
+			min: center - Vec3::splat(half_size),
+			max: center + Vec3::splat(half_size),
+		}
+	}
+
+	#[test]
+	fn outside_all_zones() {
+		let zones = [(Entity::from_raw_u32(1).unwrap(), cube(Vec3::ZERO, 1.0))];
+
+		assert_eq!(innermost_zone(Vec3::new(2.0, 0.0, 0.0), zones), None);
+		assert_eq!(innermost_zone(Vec3::ZERO, []), None);
+	}
+
+	#[test]
+	fn boundary_counts_as_inside() {
+		let zone = Entity::from_raw_u32(1).unwrap();
+		let zones = [(zone, cube(Vec3::ZERO, 1.0))];
+
+		assert_eq!(innermost_zone(Vec3::new(1.0, -1.0, 0.0), zones), Some(zone));
+	}
+
+	#[test]
+	fn innermost_nested_zone_wins() {
+		let hall = Entity::from_raw_u32(1).unwrap();
+		let closet = Entity::from_raw_u32(2).unwrap();
+		let zones = [
+			(closet, cube(Vec3::new(5.0, 0.0, 0.0), 1.0)),
+			(hall, cube(Vec3::ZERO, 10.0)),
+		];
+
+		assert_eq!(
+			innermost_zone(Vec3::new(5.0, 0.5, 0.0), zones),
+			Some(closet)
+		);
+		assert_eq!(innermost_zone(Vec3::new(-5.0, 0.0, 0.0), zones), Some(hall));
+	}
+
+	#[test]
+	fn smaller_of_overlapping_zones_wins() {
+		let big = Entity::from_raw_u32(1).unwrap();
+		let small = Entity::from_raw_u32(2).unwrap();
+		let zones = [
+			(big, cube(Vec3::ZERO, 2.0)),
+			(small, cube(Vec3::new(2.0, 0.0, 0.0), 1.0)),
+		];
+
+		// Only in the big zone.
+		assert_eq!(innermost_zone(Vec3::new(-1.5, 0.0, 0.0), zones), Some(big));
+		// In both.
+		assert_eq!(innermost_zone(Vec3::new(1.5, 0.0, 0.0), zones), Some(small));
+		// Only in the small zone.
+		assert_eq!(innermost_zone(Vec3::new(2.5, 0.0, 0.0), zones), Some(small));
+	}
+
+	#[test]
+	fn equal_zones_are_resolved_consistently() {
+		let a = Entity::from_raw_u32(1).unwrap();
+		let b = Entity::from_raw_u32(2).unwrap();
+
+		assert_eq!(
+			innermost_zone(
+				Vec3::ZERO,
+				[(b, cube(Vec3::ZERO, 1.0)), (a, cube(Vec3::ZERO, 1.0))]
+			),
+			Some(a)
+		);
+		assert_eq!(
+			innermost_zone(
+				Vec3::ZERO,
+				[(a, cube(Vec3::ZERO, 1.0)), (b, cube(Vec3::ZERO, 1.0))]
+			),
+			Some(a)
+		);
+	}
+
+	#[test]
+	fn parses_preset() {
+		assert_eq!(ReverbPreset::fgd_parse("Cave").unwrap(), ReverbPreset::Cave);
+		assert!(ReverbPreset::fgd_parse("Cathedral").is_err());
+	}
+}
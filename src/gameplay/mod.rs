@@ -21,6 +21,7 @@ pub(crate) mod dialogue_view;
 pub(crate) mod hud;
 pub(crate) mod interaction;
 pub(crate) mod level;
+pub(crate) mod music;
 pub(crate) mod npc;
 pub(crate) mod objectives;
 pub(crate) mod player;
@@ -45,6 +46,7 @@ pub(super) fn plugin(app: &mut App) {
 		core::plugin,
 		interaction::plugin,
 		hud::plugin,
+		music::plugin,
 		fever::plugin,
 		HierarchyPropagatePlugin::<RenderLayers>::new(PostUpdate),
 	));
@@ -0,0 +1,361 @@
+//! Adaptive music that follows the state of the game.
+//!
+//! Instead of playing music of its own, the [`MusicDirector`] drives the amount of
+//! the [`LayeredMusic`] spawned by the audio plugin, so that layers join in and
+//! drop out as the player makes progress. The layers all start on the same audio
+//! clock instant, and their fades are scheduled on the bar boundaries of its
+//! [`MusicGrid`](crate::audio::layers::MusicGrid).
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_yarnspinner::events::{DialogueCompleted, DialogueStarted};
+
+use crate::{
+	audio::layers::{ActiveMusic, LayeredMusic, PlayLayeredMusic, StopLayeredMusic},
+	gameplay::{
+		level::CurrentLevel,
+		objectives::{AllObjectivesDone, ObjectiveCompleted},
+	},
+	screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+	app.init_resource::<MusicDirector>()
+		.add_systems(OnExit(Screen::Gameplay), stop_music)
+		.add_systems(
+			Update,
+			(
+				decay_revelation,
+				drive_layered_music
+					.run_if(resource_changed::<MusicDirector>.or(resource_changed::<CurrentLevel>)),
+			)
+				.chain()
+				.run_if(in_state(Screen::Gameplay)),
+		)
+		.add_observer(on_dialogue_started)
+		.add_observer(on_dialogue_completed)
+		.add_observer(on_objective_completed)
+		.add_observer(on_all_objectives_done);
+}
+
+/// How long the music stays at full intensity after a revelation.
+const REVELATION_DURATION: Duration = Duration::from_secs(16);
+
+/// The current mood of the music.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MusicState {
+	/// The player is walking around.
+	#[default]
+	Exploration,
+	/// The player is talking to an NPC.
+	Conversation,
+	/// The player just made progress. This decays back to exploration or
+	/// conversation after [`REVELATION_DURATION`].
+	Revelation,
+	/// The level is over. The music stays here until it is restarted.
+	Ending,
+}
+
+impl MusicState {
+	/// The [`LayeredMusic::amount`] to play while in this state.
+	pub(crate) fn layer_amount(self) -> f32 {
+		match self {
+			Self::Exploration => 0.0,
+			Self::Conversation => 0.5,
+			Self::Revelation | Self::Ending => 1.0,
+		}
+	}
+}
+
+/// A gameplay event the music reacts to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MusicCue {
+	NpcTalkedTo,
+	DialogueFinished,
+	ObjectiveCompleted,
+	/// The player can ask the library about Dave's commune.
+	DaveQuestionUnlocked,
+	Ending,
+}
+
+impl MusicCue {
+	/// The cue with the given name, as used by the `music_cue` yarn command.
+	fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"dave_question_unlocked" => Some(Self::DaveQuestionUnlocked),
+			"objective_completed" => Some(Self::ObjectiveCompleted),
+			"ending" => Some(Self::Ending),
+			_ => None,
+		}
+	}
+}
+
+/// Decides how intense the music is based on what is happening in the game.
+#[derive(Resource, Debug)]
+pub(crate) struct MusicDirector {
+	state: MusicState,
+	/// Whether a dialogue is running, so that a revelation during it decays back
+	/// to the conversation.
+	in_dialogue: bool,
+	/// Counts down while in [`MusicState::Revelation`].
+	revelation: Timer,
+}
+
+impl Default for MusicDirector {
+	fn default() -> Self {
+		Self {
+			state: MusicState::default(),
+			in_dialogue: false,
+			revelation: Timer::new(REVELATION_DURATION, TimerMode::Once),
+		}
+	}
+}
+
+impl MusicDirector {
+	pub(crate) fn state(&self) -> MusicState {
+		self.state
+	}
+
+	/// Update the state in response to a gameplay event.
+	///
+	/// Returns `true` if the state changed.
+	pub(crate) fn handle(&mut self, cue: MusicCue) -> bool {
+		match cue {
+			MusicCue::NpcTalkedTo => self.in_dialogue = true,
+			MusicCue::DialogueFinished => self.in_dialogue = false,
+			_ => {}
+		}
+
+		let next = match (self.state, cue) {
+			(MusicState::Ending, _) => MusicState::Ending,
+			(_, MusicCue::Ending) => MusicState::Ending,
+			(_, MusicCue::ObjectiveCompleted | MusicCue::DaveQuestionUnlocked) => {
+				// Another revelation extends the current one.
+				self.revelation.reset();
+				MusicState::Revelation
+			}
+			(MusicState::Exploration, MusicCue::NpcTalkedTo) => MusicState::Conversation,
+			(MusicState::Conversation, MusicCue::DialogueFinished) => MusicState::Exploration,
+			(state, _) => state,
+		};
+
+		self.set_state(next)
+	}
+
+	/// Advance the revelation timer, decaying back to the calmer state once it
+	/// runs out.
+	///
+	/// Returns `true` if the state changed.
+	pub(crate) fn tick(&mut self, delta: Duration) -> bool {
+		if self.state != MusicState::Revelation || !self.revelation.tick(delta).is_finished() {
+			return false;
+		}
+
+		let next = if self.in_dialogue {
+			MusicState::Conversation
+		} else {
+			MusicState::Exploration
+		};
+		self.set_state(next)
+	}
+
+	fn set_state(&mut self, next: MusicState) -> bool {
+		if next == self.state {
+			return false;
+		}
+
+		self.state = next;
+		true
+	}
+}
+
+fn decay_revelation(time: Res<Time>, mut director: ResMut<MusicDirector>) {
+	// Only trigger change detection when the state actually changes.
+	if director.bypass_change_detection().tick(time.delta()) {
+		director.set_changed();
+	}
+}
+
+fn drive_layered_music(
+	director: Res<MusicDirector>,
+	current_level: Res<CurrentLevel>,
+	music: Single<(Entity, &mut LayeredMusic, Has<ActiveMusic>)>,
+	mut commands: Commands,
+) {
+	let (entity, mut layered, is_active) = music.into_inner();
+
+	// The other levels play their own level music, which the layers would
+	// otherwise be stacked on top of.
+	if *current_level != CurrentLevel::DayOne {
+		if is_active {
+			commands.trigger(StopLayeredMusic(entity));
+		}
+		return;
+	}
+
+	layered.amount = director.state().layer_amount();
+	if !is_active {
+		commands.trigger(PlayLayeredMusic(entity));
+	}
+}
+
+fn stop_music(
+	mut director: ResMut<MusicDirector>,
+	music: Single<(Entity, Has<ActiveMusic>), With<LayeredMusic>>,
+	mut commands: Commands,
+) {
+	*director = MusicDirector::default();
+
+	let (entity, is_active) = music.into_inner();
+	if is_active {
+		commands.trigger(StopLayeredMusic(entity));
+	}
+}
+
+/// Yarn command to send a cue to the [`MusicDirector`], i.e.
+/// `<<music_cue "dave_question_unlocked">>`.
+pub(crate) fn run_music_cue(In(name): In<String>, mut director: ResMut<MusicDirector>) {
+	match MusicCue::from_name(&name) {
+		Some(cue) => {
+			director.handle(cue);
+		}
+		None => warn!("Unknown music cue \"{name}\""),
+	}
+}
+
+fn on_dialogue_started(_: On<DialogueStarted>, mut director: ResMut<MusicDirector>) {
+	director.handle(MusicCue::NpcTalkedTo);
+}
+
+fn on_dialogue_completed(_: On<DialogueCompleted>, mut director: ResMut<MusicDirector>) {
+	director.handle(MusicCue::DialogueFinished);
+}
+
+fn on_objective_completed(_: On<Add, ObjectiveCompleted>, mut director: ResMut<MusicDirector>) {
+	director.handle(MusicCue::ObjectiveCompleted);
+}
+
+fn on_all_objectives_done(_: On<AllObjectivesDone>, mut director: ResMut<MusicDirector>) {
+	director.handle(MusicCue::Ending);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn conversation_returns_to_exploration() {
+		let mut director = MusicDirector::default();
+
+		assert!(director.handle(MusicCue::NpcTalkedTo));
+		assert_eq!(director.state(), MusicState::Conversation);
+
+		assert!(director.handle(MusicCue::DialogueFinished));
+		assert_eq!(director.state(), MusicState::Exploration);
+	}
+
+	#[test]
+	fn objective_completion_raises_intensity() {
+		let mut director = MusicDirector::default();
+
+		director.handle(MusicCue::NpcTalkedTo);
+		assert!(director.handle(MusicCue::ObjectiveCompleted));
+		assert_eq!(director.state(), MusicState::Revelation);
+
+		// Talking to another NPC or finishing the dialogue doesn't lower the
+		// intensity.
+		assert!(!director.handle(MusicCue::NpcTalkedTo));
+		assert!(!director.handle(MusicCue::DialogueFinished));
+		assert_eq!(director.state(), MusicState::Revelation);
+	}
+
+	#[test]
+	fn dave_question_is_a_revelation() {
+		let mut director = MusicDirector::default();
+
+		assert!(director.handle(MusicCue::DaveQuestionUnlocked));
+		assert_eq!(director.state(), MusicState::Revelation);
+		assert_eq!(
+			MusicCue::from_name("dave_question_unlocked"),
+			Some(MusicCue::DaveQuestionUnlocked)
+		);
+		assert_eq!(MusicCue::from_name("dave"), None);
+	}
+
+	#[test]
+	fn revelation_decays_without_dialogue_finishing() {
+		let mut director = MusicDirector::default();
+		director.handle(MusicCue::ObjectiveCompleted);
+
+		assert!(!director.tick(REVELATION_DURATION / 2));
+		assert_eq!(director.state(), MusicState::Revelation);
+
+		assert!(director.tick(REVELATION_DURATION / 2));
+		assert_eq!(director.state(), MusicState::Exploration);
+	}
+
+	#[test]
+	fn revelation_during_dialogue_decays_to_conversation() {
+		let mut director = MusicDirector::default();
+		director.handle(MusicCue::NpcTalkedTo);
+		director.handle(MusicCue::DaveQuestionUnlocked);
+
+		assert!(director.tick(REVELATION_DURATION));
+		assert_eq!(director.state(), MusicState::Conversation);
+
+		director.handle(MusicCue::DialogueFinished);
+		assert_eq!(director.state(), MusicState::Exploration);
+	}
+
+	#[test]
+	fn repeated_revelation_restarts_decay() {
+		let mut director = MusicDirector::default();
+		director.handle(MusicCue::ObjectiveCompleted);
+		director.tick(REVELATION_DURATION * 3 / 4);
+
+		assert!(!director.handle(MusicCue::ObjectiveCompleted));
+		assert!(!director.tick(REVELATION_DURATION / 2));
+		assert_eq!(director.state(), MusicState::Revelation);
+
+		// Decaying again needs another full revelation.
+		director.handle(MusicCue::ObjectiveCompleted);
+		assert!(director.tick(REVELATION_DURATION));
+		director.handle(MusicCue::ObjectiveCompleted);
+		assert!(!director.tick(REVELATION_DURATION / 2));
+	}
+
+	#[test]
+	fn ending_is_final() {
+		let mut director = MusicDirector::default();
+
+		assert!(director.handle(MusicCue::Ending));
+		for cue in [
+			MusicCue::NpcTalkedTo,
+			MusicCue::DialogueFinished,
+			MusicCue::ObjectiveCompleted,
+			MusicCue::DaveQuestionUnlocked,
+			MusicCue::Ending,
+		] {
+			assert!(!director.handle(cue));
+			assert_eq!(director.state(), MusicState::Ending);
+		}
+		assert!(!director.tick(REVELATION_DURATION));
+	}
+
+	#[test]
+	fn layer_amount_follows_intensity() {
+		let states = [
+			MusicState::Exploration,
+			MusicState::Conversation,
+			MusicState::Revelation,
+			MusicState::Ending,
+		];
+
+		for pair in states.windows(2) {
+			assert!(pair[0].layer_amount() <= pair[1].layer_amount());
+		}
+		assert_eq!(MusicState::Exploration.layer_amount(), 0.0);
+		assert_eq!(MusicState::Ending.layer_amount(), 1.0);
+	}
+}
@@ -27,6 +27,7 @@ pub(crate) mod dialogue;
 pub(crate) mod input;
 pub(crate) mod movement_sound;
 pub(crate) mod navmesh_position;
+mod spawn_safety;
 
 pub(super) fn plugin(app: &mut App) {
 	app.add_plugins((
@@ -37,6 +38,7 @@ pub(super) fn plugin(app: &mut App) {
 		dialogue::plugin,
 		movement_sound::plugin,
 		navmesh_position::plugin,
+		spawn_safety::plugin,
 	));
 	app.add_observer(setup_player);
 	app.load_asset::<Gltf>(Player::model_path());
@@ -0,0 +1,191 @@
+//! Makes sure the player doesn't start the level stuck inside level geometry.
+//!
+//! Before gameplay starts, a cylinder the size of the player's collider is tested
+//! against the level colliders at the spawn point. If it overlaps anything, the player is nudged
+//! to the nearest free position within [`MAX_NUDGE_DISTANCE`].
+
+use std::f32::consts::TAU;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use super::{PLAYER_HEIGHT, PLAYER_RADIUS, Player};
+use crate::{screens::Screen, third_party::avian3d::CollisionLayer};
+
+pub(super) fn plugin(app: &mut App) {
+	app.add_systems(OnEnter(Screen::Gameplay), nudge_player_out_of_geometry);
+}
+
+/// How far the player may be moved away from the spawn point.
+const MAX_NUDGE_DISTANCE: f32 = 2.0;
+/// The distance between candidate positions when searching for a free spot.
+const NUDGE_STEP: f32 = PLAYER_RADIUS / 2.0;
+/// How far the bottom of the probe is lifted off the player's feet, so that the
+/// floor the player is standing on doesn't count as an overlap.
+const FLOOR_CLEARANCE: f32 = 0.1;
+
+fn nudge_player_out_of_geometry(
+	spatial: SpatialQuery,
+	player: Single<(Entity, &mut Transform), With<Player>>,
+) {
+	let (player_entity, mut transform) = player.into_inner();
+	let spawn = transform.translation;
+
+	// The player's cylinder collider, with its bottom raised by the floor clearance.
+	let shape = Collider::cylinder(PLAYER_RADIUS, PLAYER_HEIGHT - FLOOR_CLEARANCE);
+	let probe_offset = Vec3::Y * FLOOR_CLEARANCE / 2.0;
+	let filter = SpatialQueryFilter::from_mask([
+		CollisionLayer::Default,
+		CollisionLayer::Prop,
+		CollisionLayer::Character,
+	])
+	.with_excluded_entities([player_entity]);
+	let is_free = |position: Vec3| {
+		spatial
+			.shape_intersections(&shape, position + probe_offset, Quat::IDENTITY, &filter)
+			.is_empty()
+	};
+
+	match find_free_position(spawn, MAX_NUDGE_DISTANCE, NUDGE_STEP, is_free) {
+		Some(position) if position != spawn => {
+			info!(
+				"Player spawn at {spawn} overlaps level geometry, moved it by {:.2} m to {position}",
+				spawn.distance(position)
+			);
+			transform.translation = position;
+		}
+		Some(_) => {}
+		None => {
+			warn!(
+				"Player spawn at {spawn} overlaps level geometry and there is no free position within {MAX_NUDGE_DISTANCE} m"
+			);
+		}
+	}
+}
+
+/// Find the free position closest to `origin`, searching horizontal rings of
+/// candidates `step` apart out to `max_distance`.
+///
+/// The candidates are always visited in the same order (nearest ring first, each
+/// ring counter-clockwise starting at +X), so the same layout always produces the
+/// same result.
+fn find_free_position(
+	origin: Vec3,
+	max_distance: f32,
+	step: f32,
+	mut is_free: impl FnMut(Vec3) -> bool,
+) -> Option<Vec3> {
+	if is_free(origin) {
+		return Some(origin);
+	}
+
+	let rings = (max_distance / step).floor() as u32;
+	for ring in 1..=rings {
+		let radius = ring as f32 * step;
+		// Space the candidates on this ring roughly `step` apart.
+		let candidates = (TAU * radius / step).ceil() as u32;
+		for i in 0..candidates {
+			let angle = TAU * i as f32 / candidates as f32;
+			let position = origin + Vec3::new(angle.cos(), 0.0, -angle.sin()) * radius;
+			if is_free(position) {
+				return Some(position);
+			}
+		}
+	}
+
+	None
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A synthetic level made of axis-aligned boxes, tested against a vertical
+	/// cylinder reduced to a circle on the ground plane.
+	fn layout(boxes: &[(Vec2, Vec2)]) -> impl FnMut(Vec3) -> bool {
+		move |position: Vec3| {
+			let position = Vec2::new(position.x, position.z);
+			boxes.iter().all(|&(min, max)| {
+				let closest = position.clamp(min, max);
+				closest.distance(position) >= PLAYER_RADIUS
+			})
+		}
+	}
+
+	#[test]
+	fn free_spawn_is_kept() {
+		let spawn = Vec3::new(1.0, 2.0, 3.0);
+		let is_free = layout(&[(Vec2::new(5.0, 5.0), Vec2::new(6.0, 6.0))]);
+
+		assert_eq!(
+			find_free_position(spawn, MAX_NUDGE_DISTANCE, NUDGE_STEP, is_free),
+			Some(spawn)
+		);
+	}
+
+	#[test]
+	fn spawn_inside_wall_is_moved_out() {
+		// A wall along the Z axis that the spawn point is just inside of.
+		let is_free = layout(&[(Vec2::new(-0.1, -10.0), Vec2::new(0.1, 10.0))]);
+
+		let position =
+			find_free_position(Vec3::ZERO, MAX_NUDGE_DISTANCE, NUDGE_STEP, is_free).unwrap();
+
+		assert!(position.x.abs() >= 0.1 + PLAYER_RADIUS);
+		assert!(position.distance(Vec3::ZERO) <= MAX_NUDGE_DISTANCE);
+		assert_eq!(position.y, 0.0);
+	}
+
+	#[test]
+	fn nearest_free_position_is_chosen() {
+		// A box that extends much further in -X than in +X.
+		let is_free = layout(&[(Vec2::new(-1.5, -1.5), Vec2::new(0.3, 1.5))]);
+
+		let position =
+			find_free_position(Vec3::ZERO, MAX_NUDGE_DISTANCE, NUDGE_STEP, is_free).unwrap();
+
+		assert!(position.x > 0.0, "moved to {position}");
+		assert!(position.distance(Vec3::ZERO) < 0.3 + PLAYER_RADIUS + NUDGE_STEP);
+	}
+
+	#[test]
+	fn narrow_gap_is_found() {
+		// Two boxes with a gap between them that is just wide enough for the player.
+		let gap = 2.0 * PLAYER_RADIUS + 0.05;
+		let is_free = layout(&[
+			(Vec2::new(-3.0, -3.0), Vec2::new(-0.5, 3.0)),
+			(Vec2::new(-0.5 + gap, -3.0), Vec2::new(3.0, 3.0)),
+		]);
+
+		let position = find_free_position(Vec3::ZERO, MAX_NUDGE_DISTANCE, 0.05, is_free).unwrap();
+
+		assert!(position.x > -0.5 && position.x < -0.5 + gap);
+	}
+
+	#[test]
+	fn enclosed_spawn_gives_up() {
+		let is_free = layout(&[(Vec2::splat(-5.0), Vec2::splat(5.0))]);
+
+		assert_eq!(
+			find_free_position(Vec3::ZERO, MAX_NUDGE_DISTANCE, NUDGE_STEP, is_free),
+			None
+		);
+	}
+
+	#[test]
+	fn search_is_deterministic() {
+		let boxes = [
+			(Vec2::new(-1.0, -0.2), Vec2::new(1.0, 0.2)),
+			(Vec2::new(-0.2, -1.0), Vec2::new(0.2, 1.0)),
+		];
+
+		let first = find_free_position(Vec3::ZERO, MAX_NUDGE_DISTANCE, NUDGE_STEP, layout(&boxes));
+		for _ in 0..10 {
+			assert_eq!(
+				find_free_position(Vec3::ZERO, MAX_NUDGE_DISTANCE, NUDGE_STEP, layout(&boxes)),
+				first
+			);
+		}
+		assert!(first.is_some());
+	}
+}
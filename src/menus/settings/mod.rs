@@ -0,0 +1,699 @@
+//! The settings screen accessible from the title screen.
+//! We can add all manner of settings and accessibility options here.
+//! For 3D, we'd also place the camera sensitivity and FOV here.
+//!
+//! The widgets only edit a working copy of the settings, see [`working_copy`]
+//! for how it gets applied and reverted.
+
+mod working_copy;
+
+use bevy::window::PresentMode;
+use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
+use bevy_framepace::{FramepaceSettings, Limiter};
+use bevy_seedling::prelude::*;
+use firewheel::backend::DeviceInfoSimple;
+
+use crate::ui_layout::RootWidget;
+use crate::{
+	audio::{MusicPool, perceptual::PerceptualVolumeConverter},
+	gameplay::player::camera::{CameraSensitivity, WorldModelFov},
+	menus::Menu,
+	screens::Screen,
+	theme::prelude::*,
+};
+use working_copy::{
+	AppliedSettings, ApplySettings, AudioSettings, KeepSettings, RevertCountdown, RevertSettings,
+	SettingsApplied, SettingsTab, WorkingSettings,
+};
+
+pub(super) fn plugin(app: &mut App) {
+	app.add_plugins(working_copy::plugin);
+	app.init_resource::<OutputDevices>();
+	app.add_observer(push_applied_settings);
+	#[cfg(feature = "native")]
+	app.add_observer(push_output_device);
+
+	app.add_systems(
+		OnEnter(Menu::Settings),
+		(capture_live_settings, spawn_settings_menu).chain(),
+	);
+	app.add_systems(
+		Update,
+		go_back.run_if(in_state(Menu::Settings).and(input_just_pressed(KeyCode::Escape))),
+	);
+
+	app.add_systems(
+		Update,
+		(
+			update_tab_panels,
+			update_revert_countdown_prompt,
+			update_volume_label::<GlobalVolumeLabel>,
+			update_volume_label::<MusicVolumeLabel>,
+			update_volume_label::<SfxVolumeLabel>,
+			update_output_device_label,
+			update_camera_sensitivity_label,
+			update_camera_fov_label,
+			update_vsync_label,
+			update_fps_limiter_enabled_label,
+			update_fps_limiter_target_label,
+		)
+			.run_if(in_state(Menu::Settings)),
+	);
+}
+
+fn spawn_settings_menu(mut commands: Commands, tab: Res<SettingsTab>) {
+	commands.spawn((
+		RootWidget,
+		DespawnOnExit(Menu::Settings),
+		DespawnOnExit(Screen::Gameplay),
+		GlobalZIndex(2),
+		children![
+			widget::header("Settings"),
+			(
+				Name::new("Settings Tabs"),
+				Node {
+					column_gap: Px(30.0),
+					..default()
+				},
+				children![
+					widget::button("Graphics", show_graphics_tab),
+					widget::button("Audio", show_audio_tab),
+				],
+			),
+			(
+				Name::new("Graphics Settings"),
+				settings_grid(SettingsTab::Graphics, *tab),
+				children![
+					// Camera Sensitivity
+					(
+						widget::label("Camera Sensitivity"),
+						Node {
+							justify_self: JustifySelf::End,
+							..default()
+						}
+					),
+					widget::plus_minus_bar(
+						CameraSensitivityLabel,
+						lower_camera_sensitivity,
+						raise_camera_sensitivity
+					),
+					// Camera FOV
+					(
+						widget::label("Camera FOV"),
+						Node {
+							justify_self: JustifySelf::End,
+							..default()
+						}
+					),
+					widget::plus_minus_bar(CameraFovLabel, lower_camera_fov, raise_camera_fov),
+					// VSync
+					(
+						widget::label("VSync"),
+						Node {
+							justify_self: JustifySelf::End,
+							..default()
+						}
+					),
+					widget::plus_minus_bar(VsyncLabel, disable_vsync, enable_vsync),
+					// FPS Limiter (Enable/Disable)
+					(
+						widget::label("FPS Limiter"),
+						Node {
+							justify_self: JustifySelf::End,
+							..default()
+						}
+					),
+					widget::plus_minus_bar(
+						FpsLimiterEnabledLabel,
+						disable_fps_limiter,
+						enable_fps_limiter
+					),
+					// FPS Target
+					(
+						widget::label("FPS Target"),
+						Node {
+							justify_self: JustifySelf::End,
+							..default()
+						}
+					),
+					widget::plus_minus_bar(
+						FpsLimiterTargetLabel,
+						lower_fps_target,
+						raise_fps_target
+					),
+				],
+			),
+			(
+				Name::new("Audio Settings"),
+				settings_grid(SettingsTab::Audio, *tab),
+				children![
+					(
+						widget::label("Global Volume"),
+						Node {
+							justify_self: JustifySelf::End,
+							..default()
+						}
+					),
+					widget::plus_minus_bar(
+						GlobalVolumeLabel,
+						lower_volume::<GlobalVolumeLabel>,
+						raise_volume::<GlobalVolumeLabel>
+					),
+					(
+						widget::label("Music Volume"),
+						Node {
+							justify_self: JustifySelf::End,
+							..default()
+						}
+					),
+					widget::plus_minus_bar(
+						MusicVolumeLabel,
+						lower_volume::<MusicVolumeLabel>,
+						raise_volume::<MusicVolumeLabel>
+					),
+					(
+						widget::label("Sound Effects Volume"),
+						Node {
+							justify_self: JustifySelf::End,
+							..default()
+						}
+					),
+					widget::plus_minus_bar(
+						SfxVolumeLabel,
+						lower_volume::<SfxVolumeLabel>,
+						raise_volume::<SfxVolumeLabel>
+					),
+					(
+						widget::label("Output Device"),
+						Node {
+							justify_self: JustifySelf::End,
+							..default()
+						}
+					),
+					widget::plus_minus_bar(
+						OutputDeviceLabel,
+						previous_output_device,
+						next_output_device
+					),
+				],
+			),
+			(
+				Name::new("Revert Countdown"),
+				RevertCountdownPrompt,
+				Node {
+					display: Display::None,
+					align_items: AlignItems::Center,
+					column_gap: Px(30.0),
+					..default()
+				},
+				children![
+					(widget::label(""), RevertCountdownLabel),
+					widget::button("Keep", keep_on_click),
+				],
+			),
+			(
+				Name::new("Settings Actions"),
+				Node {
+					column_gap: Px(30.0),
+					..default()
+				},
+				children![
+					widget::button("Apply", apply_on_click),
+					widget::button("Revert", revert_on_click),
+				],
+			),
+			widget::button("Back", go_back_on_click),
+		],
+	));
+}
+
+fn settings_grid(tab: SettingsTab, current_tab: SettingsTab) -> impl Bundle {
+	(
+		SettingsTabPanel(tab),
+		Node {
+			display: tab_panel_display(tab, current_tab),
+			row_gap: Px(10.0),
+			column_gap: Px(30.0),
+			grid_template_columns: RepeatedGridTrack::px(2, 400.0),
+			..default()
+		},
+	)
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct SettingsTabPanel(SettingsTab);
+
+fn tab_panel_display(tab: SettingsTab, current_tab: SettingsTab) -> Display {
+	if tab == current_tab {
+		Display::Grid
+	} else {
+		Display::None
+	}
+}
+
+fn show_graphics_tab(_on: On<Pointer<Click>>, mut tab: ResMut<SettingsTab>) {
+	*tab = SettingsTab::Graphics;
+}
+
+fn show_audio_tab(_on: On<Pointer<Click>>, mut tab: ResMut<SettingsTab>) {
+	*tab = SettingsTab::Audio;
+}
+
+fn update_tab_panels(tab: Res<SettingsTab>, mut panels: Query<(&mut Node, &SettingsTabPanel)>) {
+	for (mut node, panel) in &mut panels {
+		let display = tab_panel_display(panel.0, *tab);
+		if node.display != display {
+			node.display = display;
+		}
+	}
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct RevertCountdownPrompt;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct RevertCountdownLabel;
+
+fn update_revert_countdown_prompt(
+	countdown: Option<Res<RevertCountdown>>,
+	mut prompt: Single<&mut Node, With<RevertCountdownPrompt>>,
+	mut label: Single<&mut Text, With<RevertCountdownLabel>>,
+) {
+	let display = if countdown.is_some() {
+		Display::Flex
+	} else {
+		Display::None
+	};
+	if prompt.display != display {
+		prompt.display = display;
+	}
+	if let Some(countdown) = countdown {
+		label.0 = format!("Reverting in {}s", countdown.remaining_secs());
+	}
+}
+
+fn apply_on_click(_on: On<Pointer<Click>>, mut commands: Commands) {
+	commands.trigger(ApplySettings);
+}
+
+fn revert_on_click(_on: On<Pointer<Click>>, mut commands: Commands) {
+	commands.trigger(RevertSettings);
+}
+
+fn keep_on_click(_on: On<Pointer<Click>>, mut commands: Commands) {
+	commands.trigger(KeepSettings);
+}
+
+/// The volume buses that are exposed in the settings menu.
+type SettingsVolumeNodes = Or<(
+	With<MainBus>,
+	With<SamplerPool<MusicPool>>,
+	With<SoundEffectsBus>,
+)>;
+
+/// Seeds the working copy with the settings that are currently live,
+/// since the volumes and camera settings can also change elsewhere.
+fn capture_live_settings(
+	mut applied: ResMut<AppliedSettings>,
+	mut working: ResMut<WorkingSettings>,
+	camera_sensitivity: Res<CameraSensitivity>,
+	camera_fov: Res<WorldModelFov>,
+	volume_nodes: Query<
+		(&VolumeNode, Has<MainBus>, Has<SamplerPool<MusicPool>>),
+		SettingsVolumeNodes,
+	>,
+	mut audio_context: ResMut<AudioContext>,
+	mut output_devices: ResMut<OutputDevices>,
+) {
+	applied.graphics.camera_sensitivity = camera_sensitivity.x;
+	applied.graphics.camera_fov = camera_fov.0;
+	for (volume_node, is_main, is_music) in &volume_nodes {
+		let ticks = VolumeTicks::from(volume_node.volume);
+		match (is_main, is_music) {
+			(true, _) => applied.audio.global_volume = ticks,
+			(_, true) => applied.audio.music_volume = ticks,
+			_ => applied.audio.sfx_volume = ticks,
+		}
+	}
+	working.0 = applied.0.clone();
+
+	output_devices.0 = audio_context.with(|context| context.output_devices_simple());
+}
+
+fn push_applied_settings(
+	_on: On<SettingsApplied>,
+	applied: Res<AppliedSettings>,
+	mut window: Single<&mut Window>,
+	mut framepace: ResMut<FramepaceSettings>,
+	mut camera_sensitivity: ResMut<CameraSensitivity>,
+	mut camera_fov: ResMut<WorldModelFov>,
+	mut volume_nodes: Query<
+		(&mut VolumeNode, Has<MainBus>, Has<SamplerPool<MusicPool>>),
+		SettingsVolumeNodes,
+	>,
+) {
+	let graphics = &applied.graphics;
+	window.present_mode = if graphics.vsync {
+		PresentMode::AutoVsync
+	} else {
+		PresentMode::AutoNoVsync
+	};
+	framepace.limiter = if graphics.fps_limiter_enabled {
+		Limiter::from_framerate(graphics.fps_target as f64)
+	} else {
+		Limiter::Off
+	};
+	camera_sensitivity.0 = Vec2::splat(graphics.camera_sensitivity);
+	camera_fov.0 = graphics.camera_fov;
+
+	for (mut volume_node, is_main, is_music) in &mut volume_nodes {
+		let ticks = match (is_main, is_music) {
+			(true, _) => applied.audio.global_volume,
+			(_, true) => applied.audio.music_volume,
+			_ => applied.audio.sfx_volume,
+		};
+		volume_node.volume = ticks.into();
+	}
+}
+
+/// Mutating the stream config restarts the audio stream,
+/// so this only touches it when the device actually changed.
+#[cfg(feature = "native")]
+fn push_output_device(
+	_on: On<SettingsApplied>,
+	applied: Res<AppliedSettings>,
+	mut stream_config: ResMut<bevy_seedling::context::AudioStreamConfig>,
+) {
+	let device_id = applied
+		.audio
+		.output_device
+		.as_deref()
+		.and_then(|id| id.parse::<firewheel::cpal::cpal::DeviceId>().ok());
+	if stream_config.0.output.device_id != device_id {
+		stream_config.0.output.device_id = device_id;
+	}
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct VolumeTicks(usize);
+
+impl VolumeTicks {
+	fn increment(&mut self) {
+		self.0 = Self::MAX_TICK_COUNT.min(self.0 + 1);
+	}
+
+	fn decrement(&mut self) {
+		self.0 = self.0.saturating_sub(1);
+	}
+
+	fn fraction(&self) -> f32 {
+		self.0 as f32 / Self::MAX_TICK_COUNT as f32
+	}
+
+	fn label(&self) -> String {
+		let filled = "█".repeat(self.0);
+		let empty = " ".repeat(VolumeTicks::MAX_TICK_COUNT - self.0);
+		filled + &empty + "|"
+	}
+
+	/// How many ticks the volume slider supports
+	const MAX_TICK_COUNT: usize = 20;
+}
+
+impl From<VolumeTicks> for Volume {
+	fn from(value: VolumeTicks) -> Self {
+		PerceptualVolumeConverter::default().to_volume(value.fraction())
+	}
+}
+
+impl From<Volume> for VolumeTicks {
+	fn from(value: Volume) -> Self {
+		VolumeTicks(
+			(PerceptualVolumeConverter::default().to_perceptual(value)
+				* Self::MAX_TICK_COUNT as f32)
+				.round() as usize,
+		)
+	}
+}
+
+/// A volume label, along with the setting its bar edits.
+trait VolumeLabel: Component {
+	fn ticks(audio: &AudioSettings) -> VolumeTicks;
+	fn ticks_mut(audio: &mut AudioSettings) -> &mut VolumeTicks;
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct GlobalVolumeLabel;
+
+impl VolumeLabel for GlobalVolumeLabel {
+	fn ticks(audio: &AudioSettings) -> VolumeTicks {
+		audio.global_volume
+	}
+
+	fn ticks_mut(audio: &mut AudioSettings) -> &mut VolumeTicks {
+		&mut audio.global_volume
+	}
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct MusicVolumeLabel;
+
+impl VolumeLabel for MusicVolumeLabel {
+	fn ticks(audio: &AudioSettings) -> VolumeTicks {
+		audio.music_volume
+	}
+
+	fn ticks_mut(audio: &mut AudioSettings) -> &mut VolumeTicks {
+		&mut audio.music_volume
+	}
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct SfxVolumeLabel;
+
+impl VolumeLabel for SfxVolumeLabel {
+	fn ticks(audio: &AudioSettings) -> VolumeTicks {
+		audio.sfx_volume
+	}
+
+	fn ticks_mut(audio: &mut AudioSettings) -> &mut VolumeTicks {
+		&mut audio.sfx_volume
+	}
+}
+
+fn lower_volume<L: VolumeLabel>(_on: On<Pointer<Click>>, mut settings: ResMut<WorkingSettings>) {
+	L::ticks_mut(&mut settings.audio).decrement();
+}
+
+fn raise_volume<L: VolumeLabel>(_on: On<Pointer<Click>>, mut settings: ResMut<WorkingSettings>) {
+	L::ticks_mut(&mut settings.audio).increment();
+}
+
+fn update_volume_label<L: VolumeLabel>(
+	mut label: Single<&mut Text, With<L>>,
+	settings: Res<WorkingSettings>,
+) {
+	label.0 = L::ticks(&settings.audio).label();
+}
+
+/// The output devices offered in the settings menu, queried when it opens.
+#[derive(Resource, Debug, Default)]
+struct OutputDevices(Vec<DeviceInfoSimple>);
+
+impl OutputDevices {
+	fn name(&self, id: Option<&str>) -> String {
+		let Some(id) = id else {
+			return "System Default".into();
+		};
+		self.0
+			.iter()
+			.find(|device| device.id == id)
+			.map_or_else(|| id.to_string(), |device| device.name.clone())
+	}
+
+	/// Steps through the devices, with the system default in front.
+	fn cycle(&self, id: Option<&str>, step: isize) -> Option<String> {
+		let current = id
+			.and_then(|id| self.0.iter().position(|device| device.id == id))
+			.map_or(0, |index| index + 1);
+		let next = (current as isize + step).rem_euclid(self.0.len() as isize + 1) as usize;
+		next.checked_sub(1).map(|index| self.0[index].id.clone())
+	}
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct OutputDeviceLabel;
+
+fn previous_output_device(
+	_on: On<Pointer<Click>>,
+	mut settings: ResMut<WorkingSettings>,
+	devices: Res<OutputDevices>,
+) {
+	settings.audio.output_device = devices.cycle(settings.audio.output_device.as_deref(), -1);
+}
+
+fn next_output_device(
+	_on: On<Pointer<Click>>,
+	mut settings: ResMut<WorkingSettings>,
+	devices: Res<OutputDevices>,
+) {
+	settings.audio.output_device = devices.cycle(settings.audio.output_device.as_deref(), 1);
+}
+
+fn update_output_device_label(
+	mut label: Single<&mut Text, With<OutputDeviceLabel>>,
+	settings: Res<WorkingSettings>,
+	devices: Res<OutputDevices>,
+) {
+	label.0 = devices.name(settings.audio.output_device.as_deref());
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct CameraSensitivityLabel;
+
+fn lower_camera_sensitivity(_on: On<Pointer<Click>>, mut settings: ResMut<WorkingSettings>) {
+	const MIN_SENSITIVITY: f32 = 0.1;
+	let sensitivity = &mut settings.graphics.camera_sensitivity;
+	*sensitivity = (*sensitivity - 0.1).max(MIN_SENSITIVITY);
+}
+
+fn raise_camera_sensitivity(_on: On<Pointer<Click>>, mut settings: ResMut<WorkingSettings>) {
+	const MAX_SENSITIVITY: f32 = 20.0;
+	let sensitivity = &mut settings.graphics.camera_sensitivity;
+	*sensitivity = (*sensitivity + 0.1).min(MAX_SENSITIVITY);
+}
+
+fn update_camera_sensitivity_label(
+	mut label: Single<&mut Text, With<CameraSensitivityLabel>>,
+	settings: Res<WorkingSettings>,
+) {
+	label.0 = format!("{:.1}", settings.graphics.camera_sensitivity);
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct CameraFovLabel;
+
+fn lower_camera_fov(_on: On<Pointer<Click>>, mut settings: ResMut<WorkingSettings>) {
+	let fov = &mut settings.graphics.camera_fov;
+	*fov = (*fov - 1.0).max(45.0);
+}
+
+fn raise_camera_fov(_on: On<Pointer<Click>>, mut settings: ResMut<WorkingSettings>) {
+	let fov = &mut settings.graphics.camera_fov;
+	*fov = (*fov + 1.0).min(130.0);
+}
+
+fn update_camera_fov_label(
+	mut label: Single<&mut Text, With<CameraFovLabel>>,
+	settings: Res<WorkingSettings>,
+) {
+	label.0 = format!("{:.1}", settings.graphics.camera_fov);
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct VsyncLabel;
+
+fn enable_vsync(_on: On<Pointer<Click>>, mut settings: ResMut<WorkingSettings>) {
+	settings.graphics.vsync = true;
+}
+
+fn disable_vsync(_on: On<Pointer<Click>>, mut settings: ResMut<WorkingSettings>) {
+	settings.graphics.vsync = false;
+}
+
+fn update_vsync_label(
+	mut label: Single<&mut Text, With<VsyncLabel>>,
+	settings: Res<WorkingSettings>,
+) {
+	label.0 = if settings.graphics.vsync {
+		"On".into()
+	} else {
+		"Off".into()
+	};
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct FpsLimiterEnabledLabel;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct FpsLimiterTargetLabel;
+
+fn enable_fps_limiter(_on: On<Pointer<Click>>, mut settings: ResMut<WorkingSettings>) {
+	settings.graphics.fps_limiter_enabled = true;
+}
+
+fn disable_fps_limiter(_on: On<Pointer<Click>>, mut settings: ResMut<WorkingSettings>) {
+	settings.graphics.fps_limiter_enabled = false;
+}
+
+fn lower_fps_target(_on: On<Pointer<Click>>, mut settings: ResMut<WorkingSettings>) {
+	let min_fps = 30;
+	let step = 5;
+	let target_fps = &mut settings.graphics.fps_target;
+	*target_fps = target_fps.saturating_sub(step).max(min_fps);
+}
+
+fn raise_fps_target(_on: On<Pointer<Click>>, mut settings: ResMut<WorkingSettings>) {
+	let max_fps = 360;
+	let step = 5;
+	let target_fps = &mut settings.graphics.fps_target;
+	*target_fps = (*target_fps + step).min(max_fps);
+}
+
+fn update_fps_limiter_enabled_label(
+	mut label: Single<&mut Text, With<FpsLimiterEnabledLabel>>,
+	settings: Res<WorkingSettings>,
+) {
+	label.0 = if settings.graphics.fps_limiter_enabled {
+		"On".into()
+	} else {
+		"Off".into()
+	};
+}
+
+fn update_fps_limiter_target_label(
+	mut label: Single<&mut Text, With<FpsLimiterTargetLabel>>,
+	settings: Res<WorkingSettings>,
+) {
+	label.0 = format!("{}", settings.graphics.fps_target);
+}
+
+/// Leaving the menu discards any edits that weren't applied.
+fn go_back_on_click(
+	_on: On<Pointer<Click>>,
+	mut commands: Commands,
+	screen: Res<State<Screen>>,
+	mut next_menu: ResMut<NextState<Menu>>,
+) {
+	commands.trigger(RevertSettings);
+	next_menu.set(if screen.get() == &Screen::Title {
+		Menu::Main
+	} else {
+		Menu::Pause
+	});
+}
+
+fn go_back(
+	mut commands: Commands,
+	screen: Res<State<Screen>>,
+	mut next_menu: ResMut<NextState<Menu>>,
+) {
+	commands.trigger(RevertSettings);
+	next_menu.set(if screen.get() == &Screen::Title {
+		Menu::Main
+	} else {
+		Menu::Pause
+	});
+}
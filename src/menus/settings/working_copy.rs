@@ -0,0 +1,340 @@
+//! The state behind the settings menu, kept separate from its widgets.
+//!
+//! Widgets only ever edit [`WorkingSettings`]. Triggering [`ApplySettings`]
+//! commits the working copy to [`AppliedSettings`] and triggers
+//! [`SettingsApplied`] so the live resources can be updated, while
+//! [`RevertSettings`] throws the edits away. Applying a risky change, like
+//! switching the output device, starts a [`RevertCountdown`] that rolls back
+//! to the previous settings unless the player confirms with [`KeepSettings`].
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use super::VolumeTicks;
+
+pub(super) fn plugin(app: &mut App) {
+	app.init_resource::<SettingsTab>();
+	app.init_resource::<AppliedSettings>();
+	app.init_resource::<WorkingSettings>();
+
+	app.add_observer(apply_settings);
+	app.add_observer(revert_settings);
+	app.add_observer(keep_settings);
+
+	// Not tied to the settings menu so that a risky change is rolled back
+	// even if the player can't see or hear the menu anymore.
+	app.add_systems(
+		Update,
+		tick_revert_countdown.run_if(resource_exists::<RevertCountdown>),
+	);
+}
+
+/// How long the player has to confirm a risky change before it is rolled back.
+pub(super) const REVERT_COUNTDOWN: Duration = Duration::from_secs(10);
+
+/// The tab currently shown in the settings menu.
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[reflect(Resource)]
+pub(super) enum SettingsTab {
+	#[default]
+	Graphics,
+	Audio,
+}
+
+#[derive(Reflect, Debug, Clone, PartialEq, Default)]
+pub(super) struct Settings {
+	pub(super) graphics: GraphicsSettings,
+	pub(super) audio: AudioSettings,
+}
+
+impl Settings {
+	/// Whether going from `self` to `next` could leave the player unable to
+	/// confirm the change, e.g. because the new output device is silent.
+	fn is_risky_change_to(&self, next: &Settings) -> bool {
+		self.audio.output_device != next.audio.output_device
+	}
+}
+
+#[derive(Reflect, Debug, Clone, PartialEq)]
+pub(super) struct GraphicsSettings {
+	pub(super) vsync: bool,
+	pub(super) fps_limiter_enabled: bool,
+	pub(super) fps_target: u32,
+	pub(super) camera_fov: f32,
+	pub(super) camera_sensitivity: f32,
+}
+
+impl Default for GraphicsSettings {
+	fn default() -> Self {
+		Self {
+			vsync: true,
+			fps_limiter_enabled: false,
+			fps_target: 60,
+			camera_fov: 75.0,
+			camera_sensitivity: 1.0,
+		}
+	}
+}
+
+#[derive(Reflect, Debug, Clone, PartialEq, Default)]
+pub(super) struct AudioSettings {
+	pub(super) global_volume: VolumeTicks,
+	pub(super) music_volume: VolumeTicks,
+	pub(super) sfx_volume: VolumeTicks,
+	/// The id of the output device, or `None` to follow the system default.
+	pub(super) output_device: Option<String>,
+}
+
+/// The settings currently in effect.
+#[derive(Resource, Reflect, Debug, Clone, PartialEq, Default, Deref, DerefMut)]
+#[reflect(Resource)]
+pub(super) struct AppliedSettings(pub(super) Settings);
+
+/// The settings as edited in the menu, not yet applied.
+#[derive(Resource, Reflect, Debug, Clone, PartialEq, Default, Deref, DerefMut)]
+#[reflect(Resource)]
+pub(super) struct WorkingSettings(pub(super) Settings);
+
+/// Present while a risky change waits for the player's confirmation.
+#[derive(Resource, Debug)]
+pub(super) struct RevertCountdown {
+	timer: Timer,
+	/// The last settings the player confirmed.
+	previous: Settings,
+}
+
+impl RevertCountdown {
+	pub(super) fn remaining_secs(&self) -> u32 {
+		self.timer.remaining().as_secs_f32().ceil() as u32
+	}
+}
+
+/// Commits [`WorkingSettings`] to [`AppliedSettings`].
+#[derive(Event, Debug)]
+pub(super) struct ApplySettings;
+
+/// Discards any edits in [`WorkingSettings`]. During a [`RevertCountdown`],
+/// this also rolls back the unconfirmed change.
+#[derive(Event, Debug)]
+pub(super) struct RevertSettings;
+
+/// Confirms a risky change, cancelling the [`RevertCountdown`].
+#[derive(Event, Debug)]
+pub(super) struct KeepSettings;
+
+/// Triggered whenever [`AppliedSettings`] changed and has to be pushed to the
+/// live resources.
+#[derive(Event, Debug)]
+pub(super) struct SettingsApplied;
+
+fn apply_settings(
+	_: On<ApplySettings>,
+	mut commands: Commands,
+	working: Res<WorkingSettings>,
+	mut applied: ResMut<AppliedSettings>,
+	countdown: Option<ResMut<RevertCountdown>>,
+) {
+	if working.0 == applied.0 {
+		return;
+	}
+
+	if applied.is_risky_change_to(&working) {
+		match countdown {
+			// Keep rolling back to the last confirmed settings,
+			// but give the player the full time again.
+			Some(mut countdown) => countdown.timer.reset(),
+			None => commands.insert_resource(RevertCountdown {
+				timer: Timer::new(REVERT_COUNTDOWN, TimerMode::Once),
+				previous: applied.0.clone(),
+			}),
+		}
+	}
+
+	applied.0 = working.0.clone();
+	commands.trigger(SettingsApplied);
+}
+
+fn revert_settings(
+	_: On<RevertSettings>,
+	mut commands: Commands,
+	countdown: Option<Res<RevertCountdown>>,
+	mut applied: ResMut<AppliedSettings>,
+	mut working: ResMut<WorkingSettings>,
+) {
+	if let Some(countdown) = countdown {
+		roll_back(&mut commands, &countdown, &mut applied);
+	}
+	working.0 = applied.0.clone();
+}
+
+fn keep_settings(_: On<KeepSettings>, mut commands: Commands) {
+	commands.remove_resource::<RevertCountdown>();
+}
+
+fn tick_revert_countdown(
+	mut commands: Commands,
+	time: Res<Time<Real>>,
+	mut countdown: ResMut<RevertCountdown>,
+	mut applied: ResMut<AppliedSettings>,
+	mut working: ResMut<WorkingSettings>,
+) {
+	if !countdown.timer.tick(time.delta()).is_finished() {
+		return;
+	}
+	roll_back(&mut commands, &countdown, &mut applied);
+	working.0 = applied.0.clone();
+}
+
+fn roll_back(commands: &mut Commands, countdown: &RevertCountdown, applied: &mut AppliedSettings) {
+	applied.0 = countdown.previous.clone();
+	commands.remove_resource::<RevertCountdown>();
+	commands.trigger(SettingsApplied);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[derive(Resource, Default)]
+	struct AppliedCount(usize);
+
+	fn app() -> App {
+		let mut app = App::new();
+		app.insert_resource(Time::<Real>::default());
+		app.init_resource::<AppliedCount>();
+		app.add_plugins(plugin);
+		app.add_observer(|_: On<SettingsApplied>, mut count: ResMut<AppliedCount>| {
+			count.0 += 1;
+		});
+		app
+	}
+
+	fn advance(app: &mut App, duration: Duration) {
+		app.world_mut()
+			.resource_mut::<Time<Real>>()
+			.advance_by(duration);
+		app.update();
+	}
+
+	fn trigger<'a, E: Event<Trigger<'a>: Default>>(app: &mut App, event: E) {
+		app.world_mut().trigger(event);
+		app.world_mut().flush();
+	}
+
+	fn applied(app: &App) -> &Settings {
+		&app.world().resource::<AppliedSettings>().0
+	}
+
+	fn working(app: &App) -> &Settings {
+		&app.world().resource::<WorkingSettings>().0
+	}
+
+	fn applied_count(app: &App) -> usize {
+		app.world().resource::<AppliedCount>().0
+	}
+
+	#[test]
+	fn apply_commits_working_copy() {
+		let mut app = app();
+		app.world_mut()
+			.resource_mut::<WorkingSettings>()
+			.graphics
+			.camera_fov = 90.0;
+
+		trigger(&mut app, ApplySettings);
+
+		assert_eq!(applied(&app).graphics.camera_fov, 90.0);
+		assert_eq!(applied_count(&app), 1);
+		assert!(!app.world().contains_resource::<RevertCountdown>());
+	}
+
+	#[test]
+	fn apply_without_edits_does_nothing() {
+		let mut app = app();
+
+		trigger(&mut app, ApplySettings);
+
+		assert_eq!(applied_count(&app), 0);
+	}
+
+	#[test]
+	fn revert_discards_edits() {
+		let mut app = app();
+		app.world_mut()
+			.resource_mut::<WorkingSettings>()
+			.graphics
+			.vsync = false;
+
+		trigger(&mut app, RevertSettings);
+
+		assert!(working(&app).graphics.vsync);
+		assert_eq!(working(&app), applied(&app));
+		assert_eq!(applied_count(&app), 0);
+	}
+
+	#[test]
+	fn risky_change_rolls_back_after_countdown() {
+		let mut app = app();
+		let previous = applied(&app).clone();
+		app.world_mut()
+			.resource_mut::<WorkingSettings>()
+			.audio
+			.output_device = Some("headphones".into());
+
+		trigger(&mut app, ApplySettings);
+		assert!(app.world().contains_resource::<RevertCountdown>());
+
+		advance(&mut app, REVERT_COUNTDOWN - Duration::from_secs(1));
+		assert_eq!(
+			applied(&app).audio.output_device.as_deref(),
+			Some("headphones")
+		);
+		assert_eq!(
+			app.world().resource::<RevertCountdown>().remaining_secs(),
+			1
+		);
+
+		advance(&mut app, Duration::from_secs(1));
+		assert!(!app.world().contains_resource::<RevertCountdown>());
+		assert_eq!(applied(&app), &previous);
+		assert_eq!(working(&app), &previous);
+		assert_eq!(applied_count(&app), 2);
+	}
+
+	#[test]
+	fn keep_cancels_countdown() {
+		let mut app = app();
+		app.world_mut()
+			.resource_mut::<WorkingSettings>()
+			.audio
+			.output_device = Some("headphones".into());
+		trigger(&mut app, ApplySettings);
+
+		trigger(&mut app, KeepSettings);
+		advance(&mut app, REVERT_COUNTDOWN * 2);
+
+		assert!(!app.world().contains_resource::<RevertCountdown>());
+		assert_eq!(
+			applied(&app).audio.output_device.as_deref(),
+			Some("headphones")
+		);
+	}
+
+	#[test]
+	fn revert_during_countdown_rolls_back() {
+		let mut app = app();
+		let previous = applied(&app).clone();
+		app.world_mut()
+			.resource_mut::<WorkingSettings>()
+			.audio
+			.output_device = Some("headphones".into());
+		trigger(&mut app, ApplySettings);
+
+		trigger(&mut app, RevertSettings);
+
+		assert!(!app.world().contains_resource::<RevertCountdown>());
+		assert_eq!(applied(&app), &previous);
+		assert_eq!(working(&app), &previous);
+	}
+}
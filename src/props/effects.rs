@@ -3,7 +3,7 @@
 use bevy::{light::NotShadowCaster, prelude::*, scene::SceneInstanceReady};
 use bevy_trenchbroom::prelude::*;
 
-use std::iter;
+use std::{f32::consts::TAU, iter};
 
 use avian3d::prelude::*;
 use bevy_seedling::sample::{AudioSample, SamplePlayer};
@@ -18,8 +18,12 @@ use crate::gameplay::player::{
 };
 
 pub(super) fn plugin(app: &mut App) {
-	app.add_observer(on_special_effects);
-	app.add_systems(Update, (tick_screen_flash, tick_camera_shake));
+	app.add_observer(on_special_effects)
+		.add_observer(make_pulsing_materials_unique);
+	app.add_systems(
+		Update,
+		(tick_screen_flash, tick_camera_shake, tick_emissive_pulse),
+	);
 }
 
 pub(crate) fn disable_shadow_casting_on_instance_ready(
@@ -80,6 +84,20 @@ impl CameraShake {
 	}
 }
 
+/// Makes the meshes of a prop glow with a slow pulse so that it's easy to notice.
+#[derive(Component, Clone, Copy)]
+pub(crate) struct EmissivePulse {
+	/// The emissive color at the peak of the pulse.
+	pub(crate) color: LinearRgba,
+	/// The duration of one pulse in seconds.
+	pub(crate) period: f32,
+}
+
+/// The materials driven by an [`EmissivePulse`]. These are copies of the model's
+/// materials, so other props using the same model don't glow along.
+#[derive(Component)]
+struct PulsingMaterials(Vec<Handle<StandardMaterial>>);
+
 #[point_class(base(TargetName, Transform))]
 #[derive(Default)]
 pub struct SpecialEffectsNode {
@@ -161,6 +179,35 @@ fn on_special_effects(
 	player_velocity.0 += away * sfx_node.knockback_velocity;
 }
 
+fn make_pulsing_materials_unique(
+	ready: On<SceneInstanceReady>,
+	pulses: Query<(), With<EmissivePulse>>,
+	children: Query<&Children>,
+	mut mesh_materials: Query<&mut MeshMaterial3d<StandardMaterial>>,
+	mut materials: ResMut<Assets<StandardMaterial>>,
+	mut commands: Commands,
+) {
+	if !pulses.contains(ready.entity) {
+		return;
+	}
+
+	let mut handles = Vec::new();
+	for child in children.iter_descendants(ready.entity) {
+		let Ok(mut mesh_material) = mesh_materials.get_mut(child) else {
+			continue;
+		};
+		let Some(material) = materials.get(&mesh_material.0).cloned() else {
+			continue;
+		};
+		mesh_material.0 = materials.add(material);
+		handles.push(mesh_material.0.clone());
+	}
+
+	commands
+		.entity(ready.entity)
+		.insert(PulsingMaterials(handles));
+}
+
 // -- Systems --
 
 fn tick_screen_flash(
@@ -197,3 +244,25 @@ fn tick_camera_shake(
 		}
 	}
 }
+
+fn tick_emissive_pulse(
+	time: Res<Time>,
+	pulses: Query<(&EmissivePulse, &PulsingMaterials)>,
+	mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+	for (pulse, pulsing) in &pulses {
+		let phase = (time.elapsed_secs() / pulse.period).fract();
+		let strength = 0.5 - 0.5 * (phase * TAU).cos();
+		let emissive = pulse.color * strength;
+		for handle in &pulsing.0 {
+			// `get_mut` marks the material as changed, which re-uploads it.
+			if materials
+				.get(handle)
+				.is_some_and(|material| material.emissive != emissive)
+				&& let Some(material) = materials.get_mut(handle)
+			{
+				material.emissive = emissive;
+			}
+		}
+	}
+}
@@ -1,7 +1,7 @@
 use crate::{
 	asset_tracking::LoadResource,
 	gameplay::{TargetName, interaction::InteractEvent},
-	props::interactables::InteractableEntity,
+	props::{effects::EmissivePulse, interactables::InteractableEntity},
 	third_party::{
 		avian3d::CollisionLayer,
 		bevy_trenchbroom::{GetTrenchbroomModelPath as _, LoadTrenchbroomModel as _},
@@ -71,6 +71,9 @@ pub(super) fn plugin(app: &mut App) {
 		.load_asset::<Gltf>(Trash::model_path());
 
 	app.add_observer(on_library_light_interaction);
+
+	app.add_observer(add_camera_glow::<Cctv>)
+		.add_observer(add_camera_glow::<StaticCctv>);
 }
 
 // generic dynamic props
@@ -142,6 +145,14 @@ pub(crate) struct Cctv;
 )]
 pub(crate) struct StaticCctv;
 
+/// Playtesters had trouble spotting the cameras, so make them glow.
+fn add_camera_glow<T: Component>(add: On<Add, T>, mut commands: Commands) {
+	commands.entity(add.entity).insert(EmissivePulse {
+		color: LinearRgba::rgb(1.0, 0.15, 0.1),
+		period: 2.0,
+	});
+}
+
 // generic static props
 #[point_class(
 	base(TargetName, InteractableEntity, Transform, Visibility),
@@ -6,6 +6,7 @@ use bevy_yarnspinner::{events::DialogueCompleted, prelude::*};
 
 use crate::{
 	gameplay::{
+		music::run_music_cue,
 		objectives::{
 			complete_dialogue_objective, create_dialogue_objective, create_dialogue_subobjective,
 			get_dialogue_current_objective,
@@ -71,7 +72,8 @@ fn setup_dialogue_runner(mut commands: Commands, yarn_project: Res<YarnProject>)
 		.add_command(
 			"interact_with",
 			commands.register_system(interact_with_entity),
-		);
+		)
+		.add_command("music_cue", commands.register_system(run_music_cue));
 	dialogue_runner
 		.library_mut()
 		.add_function(